@@ -0,0 +1,287 @@
+use std::collections::HashSet;
+
+use crate::Data;
+
+/// A boolean expression over property indices, used to describe a chosen
+/// group as a formula over `data.props`.
+#[derive(Debug, Clone)]
+enum Expr {
+    Term(u8),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+impl Expr {
+    fn describe(&self, prop_names: &[String]) -> String {
+        match self {
+            Expr::Term(p) => prop_names[*p as usize].clone(),
+            Expr::Not(inner) => format!("¬{}", inner.describe(prop_names)),
+            Expr::And(terms) => terms
+                .iter()
+                .map(|t| t.describe(prop_names))
+                .collect::<Vec<_>>()
+                .join(" ∧ "),
+            Expr::Or(terms) => terms
+                .iter()
+                .map(|t| {
+                    let s = t.describe(prop_names);
+                    if matches!(t, Expr::Or(_)) { format!("({s})") } else { s }
+                })
+                .collect::<Vec<_>>()
+                .join(" ∨ "),
+        }
+    }
+}
+
+/// One prime implicant: `bits` holds the value of every non-don't-care
+/// position, `mask` marks which positions are don't-care, and `covers`
+/// lists the original minterms this implicant was built from.
+#[derive(Debug, Clone)]
+struct Implicant {
+    bits: u32,
+    mask: u32,
+    covers: Vec<u32>,
+}
+
+fn combine(a: &Implicant, b: &Implicant) -> Option<Implicant> {
+    if a.mask != b.mask {
+        return None;
+    }
+    let diff = (a.bits ^ b.bits) & !a.mask;
+    if diff.count_ones() != 1 {
+        return None;
+    }
+    let mut covers = a.covers.clone();
+    covers.extend(b.covers.iter().copied());
+    covers.sort_unstable();
+    covers.dedup();
+    Some(Implicant {
+        bits: a.bits & !diff,
+        mask: a.mask | diff,
+        covers,
+    })
+}
+
+/// Combines minterms differing in exactly one bit until nothing more
+/// combines, returning the surviving prime implicants. A combination that
+/// would match something in `off_set` is discarded rather than propagated:
+/// since generalizing an implicant can only match more minterms, it and
+/// everything built from it would be invalid too, so its components are
+/// left un-combined and kept around as (possibly still valid) primes.
+fn prime_implicants(minterms: &[u32], off_set: &[u32]) -> Vec<Implicant> {
+    let mut current: Vec<Implicant> = minterms
+        .iter()
+        .map(|&m| Implicant {
+            bits: m,
+            mask: 0,
+            covers: vec![m],
+        })
+        .collect();
+
+    let mut primes = Vec::new();
+    while !current.is_empty() {
+        let mut used = vec![false; current.len()];
+        let mut next: Vec<Implicant> = Vec::new();
+
+        for i in 0..current.len() {
+            for j in (i + 1)..current.len() {
+                if let Some(combined) = combine(&current[i], &current[j]) {
+                    if !is_valid(&combined, off_set) {
+                        continue;
+                    }
+                    used[i] = true;
+                    used[j] = true;
+                    if !next
+                        .iter()
+                        .any(|x| x.bits == combined.bits && x.mask == combined.mask)
+                    {
+                        next.push(combined);
+                    }
+                }
+            }
+        }
+
+        for (implicant, was_used) in current.into_iter().zip(used) {
+            if !was_used {
+                primes.push(implicant);
+            }
+        }
+
+        current = next;
+    }
+
+    primes
+}
+
+fn implicant_matches(implicant: &Implicant, minterm: u32) -> bool {
+    (minterm & !implicant.mask) == (implicant.bits & !implicant.mask)
+}
+
+/// Whether `implicant` matches none of `off_set`. Generalizing an implicant
+/// (growing its mask) can only ever match more minterms, never fewer, so
+/// once an implicant is invalid every implicant combined from it is invalid
+/// too.
+fn is_valid(implicant: &Implicant, off_set: &[u32]) -> bool {
+    !off_set.iter().any(|&m| implicant_matches(implicant, m))
+}
+
+/// Greedily selects a small cover of `minterms` from `primes`: essential
+/// prime implicants first, then whichever prime covers the most remaining
+/// minterms. Returns `None` if some minterm in `minterms` isn't covered by
+/// any prime in `primes` (it can't be separated from the off-set).
+fn select_cover(primes: &[Implicant], minterms: &[u32]) -> Option<Vec<Implicant>> {
+    let mut uncovered: HashSet<u32> = minterms.iter().copied().collect();
+    let mut chosen: Vec<Implicant> = Vec::new();
+
+    let already_chosen = |chosen: &[Implicant], implicant: &Implicant| {
+        chosen
+            .iter()
+            .any(|x| x.bits == implicant.bits && x.mask == implicant.mask)
+    };
+
+    let mut made_progress = true;
+    while made_progress {
+        made_progress = false;
+        for &m in &uncovered.clone() {
+            let covering: Vec<&Implicant> = primes.iter().filter(|p| p.covers.contains(&m)).collect();
+            if let [essential] = covering[..] {
+                if !already_chosen(&chosen, essential) {
+                    for &c in &essential.covers {
+                        uncovered.remove(&c);
+                    }
+                    chosen.push(essential.clone());
+                    made_progress = true;
+                }
+            }
+        }
+    }
+
+    while !uncovered.is_empty() {
+        let best = primes
+            .iter()
+            .max_by_key(|p| p.covers.iter().filter(|c| uncovered.contains(c)).count())?;
+        let newly_covered = best.covers.iter().filter(|c| uncovered.contains(c)).count();
+        if newly_covered == 0 {
+            // No surviving prime can make further progress: some minterm
+            // can't be distinguished from the off-set at all.
+            return None;
+        }
+        for c in &best.covers {
+            uncovered.remove(c);
+        }
+        if !already_chosen(&chosen, best) {
+            chosen.push(best.clone());
+        }
+    }
+
+    Some(chosen)
+}
+
+fn implicant_to_expr(implicant: &Implicant, num_props: u8) -> Expr {
+    let terms: Vec<Expr> = (0..num_props)
+        .filter(|p| implicant.mask & (1 << p) == 0)
+        .map(|p| {
+            if implicant.bits & (1 << p) != 0 {
+                Expr::Term(p)
+            } else {
+                Expr::Not(Box::new(Expr::Term(p)))
+            }
+        })
+        .collect();
+
+    if terms.len() == 1 {
+        terms.into_iter().next().unwrap()
+    } else {
+        Expr::And(terms)
+    }
+}
+
+fn cover_to_expr(cover: &[Implicant], num_props: u8) -> Expr {
+    let terms: Vec<Expr> = cover.iter().map(|i| implicant_to_expr(i, num_props)).collect();
+    if terms.len() == 1 {
+        terms.into_iter().next().unwrap()
+    } else {
+        Expr::Or(terms)
+    }
+}
+
+/// Property representation is a `u32` bitmask, so boards with more than
+/// this many properties fall back to raw name listing.
+const MAX_PROPS: usize = 32;
+
+fn minterm_of(data: &Data, name: usize) -> u32 {
+    let mut bits = 0u32;
+    for (p, (_, members)) in data.props.iter().enumerate() {
+        if members.binary_search(&name).is_ok() {
+            bits |= 1 << p;
+        }
+    }
+    bits
+}
+
+/// Describes `group_names` as an AND/OR-of-properties formula that
+/// matches exactly those names and excludes every other name in
+/// `chosen_names`, e.g. `"cold ∧ ¬forest"`. Only the group's own minterms
+/// are combined and the cover is picked greedily, so the formula is not
+/// always the smallest one that separates the group: members `cold ∧ a`
+/// and `cold ∧ b` yield two full terms even where `cold` alone would do.
+/// Returns `None` when the board
+/// has more properties than the `u32` bitmask representation can hold, or
+/// when some name in the group can't be distinguished from the rest (e.g.
+/// it shares identical property membership with a name outside the group).
+pub fn describe_group(data: &Data, group_names: &[usize], chosen_names: &[usize]) -> Option<String> {
+    if data.props.len() > MAX_PROPS {
+        return None;
+    }
+
+    let on_set: Vec<u32> = group_names.iter().map(|&n| minterm_of(data, n)).collect();
+    let off_set: Vec<u32> = chosen_names
+        .iter()
+        .filter(|n| !group_names.contains(n))
+        .map(|&n| minterm_of(data, n))
+        .collect();
+
+    let valid_primes: Vec<Implicant> = prime_implicants(&on_set, &off_set)
+        .into_iter()
+        .filter(|implicant| is_valid(implicant, &off_set))
+        .collect();
+
+    let cover = select_cover(&valid_primes, &on_set)?;
+    let expr = cover_to_expr(&cover, data.props.len() as u8);
+
+    let prop_names: Vec<String> = data.props.iter().map(|(name, _)| name.clone()).collect();
+    Some(expr.describe(&prop_names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Data;
+
+    fn board(props: &[(&str, &[usize])], num_names: usize) -> Data {
+        Data {
+            names: (0..num_names).map(|i| i.to_string()).collect(),
+            props: props.iter().map(|&(name, members)| (name.to_string(), members.to_vec())).collect(),
+            avoid_grouping: Vec::new(),
+            ignore_groups: Vec::new(),
+            group_size: 1,
+            num_groups: 1,
+        }
+    }
+
+    #[test]
+    fn describes_a_group_by_its_defining_property() {
+        let data = board(&[("cold", &[0, 1]), ("forest", &[1, 2])], 4);
+        let rule = describe_group(&data, &[0, 1], &[0, 1, 2, 3]).expect("should find a rule");
+        assert_eq!(rule, "cold");
+    }
+
+    #[test]
+    fn none_when_a_name_is_indistinguishable_from_the_off_set() {
+        // Names 0 and 1 share identical property membership, so nothing
+        // separates the chosen group ({0}) from the off-set name (1).
+        let data = board(&[("cold", &[0, 1])], 2);
+        assert_eq!(describe_group(&data, &[0], &[0, 1]), None);
+    }
+}
@@ -1,21 +1,49 @@
+use clap::Parser;
+use itertools::Itertools;
 use toml::Table;
 use z3::{
     Solver,
     ast::{Bool, atleast, atmost},
 };
 
-const BIOMES: &str = include_str!("../examples/biomes.toml");
+mod clue;
+mod uniqueness;
+
+/// Solve a Connections-style puzzle described by a TOML board file.
+#[derive(Parser, Debug)]
+struct Args {
+    /// Path to the puzzle's TOML file
+    #[arg(long)]
+    puzzle: std::path::PathBuf,
+
+    /// How many solutions to print
+    #[arg(long, default_value_t = 10)]
+    count: usize,
+
+    /// Print every solution instead of stopping after `--count`
+    #[arg(long)]
+    all: bool,
+
+    /// Instead of solving, check whether the board has a unique solution
+    /// and suggest `limits.avoid-grouping` additions if it doesn't
+    #[arg(long)]
+    check_unique: bool,
+}
 
 #[derive(Debug)]
-struct Data {
+pub(crate) struct Data {
     names: Vec<String>,
     props: Vec<(String, Vec<usize>)>,
     avoid_grouping: Vec<Vec<usize>>,
     ignore_groups: Vec<usize>,
+    /// How many names make up a chosen group (4 in standard Connections).
+    group_size: usize,
+    /// How many groups the board is partitioned into (4 in standard Connections).
+    num_groups: usize,
 }
 
-fn create_data() -> Data {
-    let table = BIOMES.parse::<Table>().unwrap();
+fn create_data(puzzle: &str) -> Data {
+    let table = puzzle.parse::<Table>().unwrap();
     let mut names: Vec<String> = table["names"]
         .as_array()
         .unwrap()
@@ -98,11 +126,28 @@ fn create_data() -> Data {
             (Vec::new(), Vec::new())
         };
 
+    let (group_size, num_groups) =
+        if let Some(config) = table.get("config").map(|x| x.as_table().unwrap()) {
+            let group_size = config
+                .get("group-size")
+                .map(|x| x.as_integer().unwrap() as usize)
+                .unwrap_or(4);
+            let num_groups = config
+                .get("num-groups")
+                .map(|x| x.as_integer().unwrap() as usize)
+                .unwrap_or(4);
+            (group_size, num_groups)
+        } else {
+            (4, 4)
+        };
+
     Data {
         names,
         props,
         avoid_grouping,
         ignore_groups,
+        group_size,
+        num_groups,
     }
 }
 
@@ -110,7 +155,7 @@ fn exactly<'a, I: IntoIterator<Item = &'a Bool> + Clone>(args: I, k: u32) -> Boo
     Bool::and(&[atleast(args.clone(), k), atmost(args, k)])
 }
 
-fn intersection(v1: &[usize], v2: &[usize]) -> Vec<usize> {
+pub(crate) fn intersection(v1: &[usize], v2: &[usize]) -> Vec<usize> {
     debug_assert!(v1.is_sorted());
     debug_assert!(v2.is_sorted());
     let mut out = Vec::new();
@@ -130,38 +175,56 @@ fn intersection(v1: &[usize], v2: &[usize]) -> Vec<usize> {
     out
 }
 
-fn main() {
-    let data = create_data();
+fn print_groups(data: &Data, chosen_groups: &[usize], chosen_names: &[usize]) {
+    println!("GROUPS: ");
+    for i in chosen_groups {
+        let including = intersection(chosen_names, &data.props[*i].1);
+        let including_names: Vec<&String> = including.iter().map(|x| &data.names[*x]).collect();
+        match clue::describe_group(data, &including, chosen_names) {
+            Some(rule) => println!("{}: {:?} ({rule})", data.props[*i].0, including_names),
+            None => println!("{}: {:?} ", data.props[*i].0, including_names),
+        }
+    }
+}
 
+/// Builds the z3 solver for `data`, restricted to `candidate_props`, with
+/// `extra_avoid` treated as additional `limits.avoid-grouping` entries on
+/// top of the ones already in `data`.
+pub(crate) fn build_solver(
+    data: &Data,
+    candidate_props: &[usize],
+    extra_avoid: &[Vec<usize>],
+) -> (Solver, Vec<Bool>, Vec<Bool>) {
     let name_variables: Vec<Bool> = data.names.iter().map(|s| Bool::fresh_const(s)).collect();
-    let group_variables: Vec<Bool> = data.props.iter().map(|s| Bool::fresh_const(&s.0)).collect();
+    let group_variables: Vec<Bool> = candidate_props
+        .iter()
+        .map(|&i| Bool::fresh_const(&data.props[i].0))
+        .collect();
 
-    // For the ith name, which groups is it in
+    // For the ith name, which (candidate) groups is it in
     let groups_of_names: Vec<Vec<&Bool>> = (0..data.names.len())
         .map(|i| {
             let mut out = Vec::new();
-            for j in 0..data.props.len() {
+            for (k, &j) in candidate_props.iter().enumerate() {
                 if data.props[j].1.contains(&i) {
-                    out.push(&group_variables[j]);
+                    out.push(&group_variables[k]);
                 }
             }
             out
         })
         .collect();
 
-    // Pairs of groups and all values in both groups
+    // Pairs of candidate groups and all values in both groups
     let mut pairwise: Vec<(&Bool, &Bool, Vec<usize>)> = Vec::new();
 
-    for i in 0..group_variables.len() {
-        for j in 0..i {
-            let name_i = &group_variables[i];
-            let members_i = &data.props[i].1;
-            let name_j = &group_variables[j];
-            let members_j = &data.props[j].1;
+    for (j, i) in (0..candidate_props.len()).tuple_combinations() {
+        let name_i = &group_variables[i];
+        let members_i = &data.props[candidate_props[i]].1;
+        let name_j = &group_variables[j];
+        let members_j = &data.props[candidate_props[j]].1;
 
-            let inter = intersection(members_i, members_j);
-            pairwise.push((name_i, name_j, inter));
-        }
+        let inter = intersection(members_i, members_j);
+        pairwise.push((name_i, name_j, inter));
     }
 
     let solver = Solver::new();
@@ -173,66 +236,81 @@ fn main() {
         solver.assert(name_variable.implies(Bool::or(groups)));
     }
 
-    // If two groups are included and some element in their intersection, then there has to be four in their
-    // intersection (which means that all are in their intersection).
+    // If two groups are included and some element in their intersection, then there has to be `group_size`
+    // in their intersection (which means that all are in their intersection).
     for (a, b, both) in pairwise {
         let inter_bool: Vec<&Bool> = both.iter().map(|&x| &name_variables[x]).collect();
         let some_inter = Bool::or(&inter_bool);
         let both_bool = Bool::and(&[a, b, &some_inter]);
-        let has_four = exactly(both.iter().map(|&x| &name_variables[x]), 4);
-        solver.assert(both_bool.implies(has_four));
+        let has_group_size = exactly(both.iter().map(|&x| &name_variables[x]), data.group_size as u32);
+        solver.assert(both_bool.implies(has_group_size));
     }
 
-    // If we include a group, then we include exactly four of it's members
-    for (i, group) in group_variables.iter().enumerate() {
-        let members = &data.props[i].1;
-        let has_four = exactly(members.iter().map(|&x| &name_variables[x]), 4);
-        solver.assert(group.implies(has_four));
+    // If we include a group, then we include exactly `group_size` of its members
+    for (k, group) in group_variables.iter().enumerate() {
+        let members = &data.props[candidate_props[k]].1;
+        let has_group_size = exactly(members.iter().map(|&x| &name_variables[x]), data.group_size as u32);
+        solver.assert(group.implies(has_group_size));
     }
 
     // Groups can't be active together with any pair of members that we're avoiding
     // grouping together.
-    for (i, group) in group_variables.iter().enumerate() {
-        let members = &data.props[i].1;
-        for avoid in &data.avoid_grouping {
+    for (k, group) in group_variables.iter().enumerate() {
+        let members = &data.props[candidate_props[k]].1;
+        for avoid in data.avoid_grouping.iter().chain(extra_avoid) {
             let inter = intersection(members, avoid);
-            for p in 0..inter.len() {
-                for q in 0..p {
-                    let a = inter[p];
-                    let b = inter[q];
-                    let bool_a = &name_variables[a];
-                    let bool_b = &name_variables[b];
-                    solver.assert(Bool::and(&[group, bool_a, bool_b]).not());
-                }
+            for (&a, &b) in inter.iter().tuple_combinations() {
+                let bool_a = &name_variables[a];
+                let bool_b = &name_variables[b];
+                solver.assert(Bool::and(&[group, bool_a, bool_b]).not());
             }
         }
     }
 
     // We don't choose a group that we're ignoring
-    for ignored in data.ignore_groups {
-        solver.assert(group_variables[ignored].not());
+    for &ignored in &data.ignore_groups {
+        if let Some(k) = candidate_props.iter().position(|&i| i == ignored) {
+            solver.assert(group_variables[k].not());
+        }
+    }
+
+    // We have group_size * num_groups names in total
+    let total = (data.group_size * data.num_groups) as u32;
+    solver.assert(exactly(name_variables.iter(), total));
+
+    (solver, name_variables, group_variables)
+}
+
+fn main() {
+    let args = Args::parse();
+    let puzzle = std::fs::read_to_string(&args.puzzle).expect("failed to read puzzle file");
+    let data = create_data(&puzzle);
+
+    // No pre-solve step: z3 sees every prop.
+    let candidate_props: Vec<usize> = (0..data.props.len()).collect();
+
+    if args.check_unique {
+        uniqueness::check_uniqueness(&data, &candidate_props);
+        return;
     }
 
-    // We have 16 in total
-    solver.assert(exactly(name_variables.iter(), 16));
+    let (solver, name_variables, group_variables) = build_solver(&data, &candidate_props, &[]);
 
-    // let res = solver.check();
-    // println!("{:?}", res);
+    let count = if args.all { usize::MAX } else { args.count };
 
     for (name_solution, group_solution) in solver
         .solutions((&name_variables, &group_variables), false)
-        .take(10)
+        .take(count)
     {
         let values: Vec<bool> = group_solution
             .iter()
             .map(|x| x.as_bool().unwrap())
             .collect();
-        let chosen_groups: Vec<usize> = data
-            .props
+        let chosen_groups: Vec<usize> = candidate_props
             .iter()
             .enumerate()
-            .filter(|(i, _)| values[*i])
-            .map(|x| x.0)
+            .filter(|(k, _)| values[*k])
+            .map(|(_, &i)| i)
             .collect();
         debug_assert!(chosen_groups.is_sorted());
 
@@ -246,11 +324,6 @@ fn main() {
             .collect();
         debug_assert!(chosen_names.is_sorted());
 
-        println!("GROUPS: ");
-        for i in &chosen_groups {
-            let including = intersection(&chosen_names, &data.props[*i].1);
-            let including_names: Vec<&String> = including.iter().map(|x| &data.names[*x]).collect();
-            println!("{}: {:?} ", data.props[*i].0, including_names);
-        }
+        print_groups(&data, &chosen_groups, &chosen_names);
     }
 }
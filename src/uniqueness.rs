@@ -0,0 +1,199 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+use z3::ast::Bool;
+
+use crate::{Data, build_solver, intersection};
+
+/// Runs the first two solutions for `data`; if a second one exists, reports
+/// the board as ambiguous and suggests `limits.avoid-grouping` additions
+/// that collapse it back down to a single solution.
+pub fn check_uniqueness(data: &Data, candidate_props: &[usize]) {
+    let (solver, name_variables, group_variables) = build_solver(data, candidate_props, &[]);
+    let mut solutions = solver.solutions((&name_variables, &group_variables), false);
+
+    let Some(solution_a) = solutions.next() else {
+        println!("Board has no solutions.");
+        return;
+    };
+    let Some(solution_b) = solutions.next() else {
+        println!("Board is unique.");
+        return;
+    };
+
+    println!("Board is ambiguous: found at least two valid solutions.");
+
+    let pairs_a = grouped_pairs(data, candidate_props, &solution_a);
+    let pairs_b = grouped_pairs(data, candidate_props, &solution_b);
+    let differing: Vec<(usize, usize)> = pairs_a.symmetric_difference(&pairs_b).copied().collect();
+
+    match minimal_disambiguating_set(data, candidate_props, &differing) {
+        Disambiguation::Found(suggestions) => {
+            println!("Suggested additions to limits.avoid-grouping:");
+            for (a, b) in suggestions {
+                println!("  [\"{}\", \"{}\"]", data.names[a], data.names[b]);
+            }
+        }
+        Disambiguation::NoneExists => {
+            println!("No combination of avoid-grouping additions disambiguates this board.");
+        }
+        Disambiguation::GaveUp => {
+            println!(
+                "Board is ambiguous across too many name-pairs to search for a minimal \
+                 avoid-grouping set; narrow it down manually."
+            );
+        }
+    }
+}
+
+/// Upper bound on how many avoid-grouping subsets `minimal_disambiguating_set`
+/// will run the solver on before giving up. Without this, a board ambiguous
+/// across many name-pairs makes the subset search (which is exponential in
+/// `differing.len()`) run effectively forever.
+const MAX_DISAMBIGUATION_ATTEMPTS: usize = 200;
+
+/// Outcome of searching for a minimal disambiguating set.
+enum Disambiguation {
+    /// The smallest subset found that collapses the board to one solution.
+    Found(Vec<(usize, usize)>),
+    /// Every subset was tried and none disambiguates the board.
+    NoneExists,
+    /// The search space was too large to exhaust within the attempt budget.
+    GaveUp,
+}
+
+/// Searches `differing` pairs for the smallest subset that, added to
+/// `limits.avoid-grouping` all at once, collapses the board to exactly one
+/// solution. Tries subsets in increasing size so the first hit is minimal,
+/// giving up after `MAX_DISAMBIGUATION_ATTEMPTS` solver calls.
+fn minimal_disambiguating_set(
+    data: &Data,
+    candidate_props: &[usize],
+    differing: &[(usize, usize)],
+) -> Disambiguation {
+    let mut attempts = 0;
+    for k in 1..=differing.len() {
+        for candidate in differing.iter().copied().combinations(k) {
+            if attempts >= MAX_DISAMBIGUATION_ATTEMPTS {
+                return Disambiguation::GaveUp;
+            }
+            attempts += 1;
+            if disambiguates(data, candidate_props, &candidate) {
+                return Disambiguation::Found(candidate);
+            }
+        }
+    }
+    Disambiguation::NoneExists
+}
+
+/// All pairs of names that end up in the same chosen group in `solution`.
+fn grouped_pairs(
+    data: &Data,
+    candidate_props: &[usize],
+    solution: &(Vec<Bool>, Vec<Bool>),
+) -> HashSet<(usize, usize)> {
+    let (name_solution, group_solution) = solution;
+
+    let name_values: Vec<bool> = name_solution.iter().map(|x| x.as_bool().unwrap()).collect();
+    let chosen_names: Vec<usize> = (0..data.names.len()).filter(|&i| name_values[i]).collect();
+
+    let group_values: Vec<bool> = group_solution.iter().map(|x| x.as_bool().unwrap()).collect();
+
+    let mut pairs = HashSet::new();
+    for (k, &i) in candidate_props.iter().enumerate() {
+        if !group_values[k] {
+            continue;
+        }
+        let including = intersection(&chosen_names, &data.props[i].1);
+        for (&a, &b) in including.iter().tuple_combinations() {
+            pairs.insert((a, b));
+        }
+    }
+    pairs
+}
+
+/// Whether forbidding every pair in `pairs` via `limits.avoid-grouping`
+/// collapses the board down to exactly one solution.
+fn disambiguates(data: &Data, candidate_props: &[usize], pairs: &[(usize, usize)]) -> bool {
+    let extra_avoid: Vec<Vec<usize>> = pairs.iter().map(|&(a, b)| vec![a, b]).collect();
+    let (solver, name_variables, group_variables) = build_solver(data, candidate_props, &extra_avoid);
+    solver
+        .solutions((&name_variables, &group_variables), false)
+        .take(2)
+        .count()
+        == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Partitioning 4 names into 2 pairs has exactly 3 distinct solutions,
+    /// and no single avoid-grouping pair can cut that down to one: forbidding
+    /// one partition's pairing still leaves the other two standing.
+    fn pairing_board() -> Data {
+        Data {
+            names: (0..4).map(|i| i.to_string()).collect(),
+            props: vec![
+                ("P01".to_string(), vec![0, 1]),
+                ("P23".to_string(), vec![2, 3]),
+                ("P02".to_string(), vec![0, 2]),
+                ("P13".to_string(), vec![1, 3]),
+                ("P03".to_string(), vec![0, 3]),
+                ("P12".to_string(), vec![1, 2]),
+            ],
+            avoid_grouping: Vec::new(),
+            ignore_groups: Vec::new(),
+            group_size: 2,
+            num_groups: 2,
+        }
+    }
+
+    #[test]
+    fn finds_minimal_two_pair_disambiguating_set() {
+        let data = pairing_board();
+        let candidate_props: Vec<usize> = (0..data.props.len()).collect();
+
+        let (solver, name_variables, group_variables) = build_solver(&data, &candidate_props, &[]);
+        let mut solutions = solver.solutions((&name_variables, &group_variables), false);
+        let solution_a = solutions.next().expect("board has a solution");
+        let solution_b = solutions.next().expect("board is ambiguous");
+        assert!(solutions.next().is_some(), "board should have a third partition");
+        assert!(solutions.next().is_none(), "board should have exactly 3 partitions");
+
+        let pairs_a = grouped_pairs(&data, &candidate_props, &solution_a);
+        let pairs_b = grouped_pairs(&data, &candidate_props, &solution_b);
+        let differing: Vec<(usize, usize)> = pairs_a.symmetric_difference(&pairs_b).copied().collect();
+
+        for &pair in &differing {
+            assert!(
+                !disambiguates(&data, &candidate_props, &[pair]),
+                "a single pair shouldn't be enough to disambiguate this board"
+            );
+        }
+
+        match minimal_disambiguating_set(&data, &candidate_props, &differing) {
+            Disambiguation::Found(suggestion) => {
+                assert_eq!(suggestion.len(), 2);
+                assert!(disambiguates(&data, &candidate_props, &suggestion));
+            }
+            _ => panic!("expected a minimal disambiguating set of size 2"),
+        }
+    }
+
+    #[test]
+    fn gives_up_past_the_attempt_budget_instead_of_searching_forever() {
+        // 2 * MAX_DISAMBIGUATION_ATTEMPTS differing pairs guarantees the
+        // k=1 pass alone blows through the budget before finding anything.
+        let data = pairing_board();
+        let candidate_props: Vec<usize> = (0..data.props.len()).collect();
+        let differing: Vec<(usize, usize)> = (0..2 * MAX_DISAMBIGUATION_ATTEMPTS)
+            .map(|i| (i, i + 1))
+            .collect();
+
+        match minimal_disambiguating_set(&data, &candidate_props, &differing) {
+            Disambiguation::GaveUp => {}
+            _ => panic!("expected the search to give up past the attempt budget"),
+        }
+    }
+}